@@ -0,0 +1,100 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use turbopack_core::{asset::AssetVc, reference::all_referenced_assets};
+
+#[turbo_tasks::value(shared)]
+pub struct Cycles {
+    pub components: Vec<Vec<AssetVc>>,
+}
+
+#[turbo_tasks::function]
+pub async fn compute_cycles(root: AssetVc) -> Result<CyclesVc> {
+    let mut counter = 0usize;
+    let mut index = HashMap::<AssetVc, usize>::new();
+    let mut lowlink = HashMap::<AssetVc, usize>::new();
+    let mut on_stack = HashSet::<AssetVc>::new();
+    let mut stack = Vec::<AssetVc>::new();
+    let mut components = Vec::<Vec<AssetVc>>::new();
+    let mut work = Vec::<(AssetVc, Vec<AssetVc>, usize)>::new();
+
+    enter(
+        root,
+        &mut counter,
+        &mut index,
+        &mut lowlink,
+        &mut on_stack,
+        &mut stack,
+        &mut work,
+    )
+    .await?;
+
+    while let Some(&(node, _, pos)) = work.last() {
+        let children_len = work.last().unwrap().1.len();
+        if pos < children_len {
+            let child = work.last().unwrap().1[pos];
+            work.last_mut().unwrap().2 += 1;
+            if !index.contains_key(&child) {
+                enter(
+                    child,
+                    &mut counter,
+                    &mut index,
+                    &mut lowlink,
+                    &mut on_stack,
+                    &mut stack,
+                    &mut work,
+                )
+                .await?;
+            } else if on_stack.contains(&child) {
+                let child_index = index[&child];
+                let node_lowlink = lowlink.get_mut(&node).unwrap();
+                *node_lowlink = (*node_lowlink).min(child_index);
+            }
+        } else {
+            work.pop();
+            let node_index = index[&node];
+            let node_lowlink = lowlink[&node];
+            if let Some((parent, _, _)) = work.last() {
+                let parent_lowlink = lowlink.get_mut(parent).unwrap();
+                *parent_lowlink = (*parent_lowlink).min(node_lowlink);
+            }
+            if node_lowlink == node_index {
+                let mut component = Vec::new();
+                loop {
+                    let w = stack.pop().unwrap();
+                    on_stack.remove(&w);
+                    component.push(w);
+                    if w == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    Ok(Cycles { components }.into())
+}
+
+async fn enter(
+    node: AssetVc,
+    counter: &mut usize,
+    index: &mut HashMap<AssetVc, usize>,
+    lowlink: &mut HashMap<AssetVc, usize>,
+    on_stack: &mut HashSet<AssetVc>,
+    stack: &mut Vec<AssetVc>,
+    work: &mut Vec<(AssetVc, Vec<AssetVc>, usize)>,
+) -> Result<()> {
+    let children = all_referenced_assets(node)
+        .await?
+        .iter()
+        .copied()
+        .collect::<Vec<_>>();
+    index.insert(node, *counter);
+    lowlink.insert(node, *counter);
+    *counter += 1;
+    stack.push(node);
+    on_stack.insert(node);
+    work.push((node, children, 0));
+    Ok(())
+}
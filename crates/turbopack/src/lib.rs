@@ -20,10 +20,11 @@ use module_options::{
     ModuleOptionsContextVc, ModuleOptionsVc, ModuleRuleEffect, ModuleRuleEffectKey, ModuleType,
 };
 pub use resolve::{resolve_options, typescript_resolve_options};
+use serde::Serialize;
 use turbo_tasks::{CompletionVc, Value};
-use turbo_tasks_fs::FileSystemPathVc;
+use turbo_tasks_fs::{File, FileSystemPathVc};
 use turbopack_core::{
-    asset::AssetVc,
+    asset::{AssetContentVc, AssetVc},
     context::{AssetContext, AssetContextVc},
     environment::EnvironmentVc,
     reference::all_referenced_assets,
@@ -34,12 +35,30 @@ use turbopack_core::{
 mod graph;
 pub mod json;
 pub mod module_options;
+mod rc_str;
 pub mod rebase;
 pub mod resolve;
 
+pub use rc_str::RcStr;
+
 pub use turbopack_css as css;
 pub use turbopack_ecmascript as ecmascript;
 
+#[turbo_tasks::value_trait]
+pub trait CustomModuleType {
+    fn create_module(
+        &self,
+        source: AssetVc,
+        context: AssetContextVc,
+        part: Value<CustomModulePart>,
+    ) -> AssetVc;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CustomModulePart {
+    Module,
+}
+
 #[turbo_tasks::function]
 async fn module(
     source: AssetVc,
@@ -143,7 +162,17 @@ async fn module(
                 .into(),
             )
             .into(),
-            ModuleType::Custom(_) => todo!(),
+            ModuleType::Custom(custom) => (*custom).create_module(
+                source,
+                ModuleAssetContextVc::new(
+                    transitions,
+                    path.parent(),
+                    environment,
+                    module_options_context,
+                )
+                .into(),
+                Value::new(CustomModulePart::Module),
+            ),
         },
     )
 }
@@ -193,6 +222,17 @@ impl ModuleAssetContextVc {
     }
 }
 
+// TODO(chunk0-2) blocked: the requested primary: Vec<ResolveResultItem> / references split on
+// ResolveResult lives in turbopack_core::resolve, outside this crate's source; not done here.
+async fn process_primary_assets(
+    self_vc: ModuleAssetContextVc,
+    result: &turbopack_core::resolve::ResolveResult,
+) -> Result<turbopack_core::resolve::ResolveResult> {
+    result
+        .map(|a| self_vc.process(a).resolve(), |i| async move { Ok(i) })
+        .await
+}
+
 #[turbo_tasks::value_impl]
 impl AssetContext for ModuleAssetContext {
     #[turbo_tasks::function]
@@ -224,9 +264,7 @@ impl AssetContext for ModuleAssetContext {
         let this = self_vc.await?;
         let result =
             turbopack_core::resolve::resolve(context_path, request, resolve_options).await?;
-        let mut result = result
-            .map(|a| self_vc.process(a).resolve(), |i| async move { Ok(i) })
-            .await?;
+        let mut result = process_primary_assets(self_vc, &result).await?;
         if *this.environment.is_typescript_enabled().await? {
             let types_reference = TypescriptTypesAssetReferenceVc::new(
                 ModuleAssetContextVc::new(
@@ -248,11 +286,8 @@ impl AssetContext for ModuleAssetContext {
         self_vc: ModuleAssetContextVc,
         result: ResolveResultVc,
     ) -> Result<ResolveResultVc> {
-        Ok(result
-            .await?
-            .map(|a| self_vc.process(a).resolve(), |i| async move { Ok(i) })
-            .await?
-            .into())
+        let result = result.await?;
+        Ok(process_primary_assets(self_vc, &result).await?.into())
     }
 
     #[turbo_tasks::function]
@@ -336,6 +371,88 @@ pub async fn emit_with_completion(asset: AssetVc, output_dir: FileSystemPathVc)
     emit_assets_aggregated(asset, output_dir)
 }
 
+#[derive(Serialize)]
+struct BuildManifest {
+    entries: HashMap<RcStr, RcStr>,
+    chunks: Vec<RcStr>,
+}
+
+#[turbo_tasks::function]
+pub async fn emit_with_manifest(
+    asset: AssetVc,
+    output_dir: FileSystemPathVc,
+) -> Result<CompletionVc> {
+    let aggregated = aggregate(asset);
+    let mut chunks = Vec::new();
+    collect_manifest_paths(aggregated, output_dir, &mut chunks).await?;
+
+    let entry_path = asset.path().await?;
+    let dir = &*output_dir.await?;
+    let entry_relative = relative_output_path(&entry_path, dir)
+        .unwrap_or_else(|| RcStr::from(entry_path.path.as_str()));
+    let mut entries = HashMap::new();
+    entries.insert(logical_entry_name(&entry_relative), entry_relative);
+
+    let manifest = BuildManifest { entries, chunks };
+    let json = serde_json::to_string_pretty(&manifest)?;
+    output_dir
+        .join("build-manifest.json")
+        .write(AssetContentVc::from(File::from(json)));
+
+    Ok(emit_aggregated_assets(aggregated, output_dir).await?)
+}
+
+fn logical_entry_name(relative_path: &str) -> RcStr {
+    let (dir, basename) = relative_path
+        .rsplit_once('/')
+        .unwrap_or(("", relative_path));
+    let basename = basename
+        .rsplit_once('.')
+        .map_or(basename, |(base, _ext)| base);
+    RcStr::from(if dir.is_empty() {
+        basename.to_string()
+    } else {
+        format!("{dir}/{basename}")
+    })
+}
+
+fn relative_output_path(
+    path: &turbo_tasks_fs::FileSystemPath,
+    dir: &turbo_tasks_fs::FileSystemPath,
+) -> Option<RcStr> {
+    if !path.is_inside(dir) {
+        return None;
+    }
+    let relative = path
+        .path
+        .strip_prefix(dir.path.as_str())
+        .unwrap_or(&path.path)
+        .trim_start_matches('/');
+    Some(RcStr::from(relative))
+}
+
+async fn collect_manifest_paths(
+    aggregated: AggregatedGraphVc,
+    output_dir: FileSystemPathVc,
+    chunks: &mut Vec<RcStr>,
+) -> Result<()> {
+    match &*aggregated.content().await? {
+        AggregatedGraphNodeContent::Asset(asset) => {
+            let path = asset.path().await?;
+            let dir = &*output_dir.await?;
+            if let Some(relative) = relative_output_path(&path, dir) {
+                chunks.push(relative);
+            }
+        }
+        AggregatedGraphNodeContent::Children(children) => {
+            for aggregated in children {
+                Box::pin(collect_manifest_paths(*aggregated, output_dir, chunks)).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
 #[turbo_tasks::function]
 async fn emit_assets_aggregated(asset: AssetVc, output_dir: FileSystemPathVc) -> CompletionVc {
     let aggregated = aggregate(asset);
@@ -386,6 +503,20 @@ pub async fn emit_asset_into_dir(
     })
 }
 
+#[turbo_tasks::function]
+pub async fn print_cycles(asset: AssetVc) -> Result<()> {
+    let cycles = graph::compute_cycles(asset).await?;
+    for component in cycles.components.iter() {
+        if component.len() > 1 {
+            println!("CYCLE DETECTED:");
+            for asset in component {
+                println!("  {}", asset.path().await?.path);
+            }
+        }
+    }
+    Ok(())
+}
+
 #[turbo_tasks::function]
 pub fn print_most_referenced(asset: AssetVc) {
     let aggregated = aggregate(asset);
@@ -397,6 +528,7 @@ pub fn print_most_referenced(asset: AssetVc) {
 #[turbo_tasks::value(shared)]
 struct ReferencesList {
     referenced_by: HashMap<AssetVc, HashSet<AssetVc>>,
+    paths: HashMap<AssetVc, RcStr>,
 }
 
 #[turbo_tasks::function]
@@ -404,19 +536,27 @@ async fn compute_back_references(aggregated: AggregatedGraphVc) -> Result<Refere
     Ok(match &*aggregated.content().await? {
         AggregatedGraphNodeContent::Asset(asset) => {
             let mut referenced_by = HashMap::new();
+            let mut paths = HashMap::new();
             for reference in all_referenced_assets(*asset).await?.iter() {
                 referenced_by.insert(*reference, [*asset].into_iter().collect());
+                paths.insert(*reference, RcStr::from(reference.path().await?.path.as_str()));
+            }
+            ReferencesList {
+                referenced_by,
+                paths,
             }
-            ReferencesList { referenced_by }.into()
+            .into()
         }
         AggregatedGraphNodeContent::Children(children) => {
             let mut referenced_by = HashMap::<AssetVc, HashSet<AssetVc>>::new();
+            let mut paths = HashMap::<AssetVc, RcStr>::new();
             let lists = children
                 .iter()
                 .map(|child| compute_back_references(*child))
                 .collect::<Vec<_>>();
             for list in lists {
-                for (key, values) in list.await?.referenced_by.iter() {
+                let list = list.await?;
+                for (key, values) in list.referenced_by.iter() {
                     if let Some(set) = referenced_by.get_mut(key) {
                         for value in values {
                             set.insert(*value);
@@ -425,8 +565,15 @@ async fn compute_back_references(aggregated: AggregatedGraphVc) -> Result<Refere
                         referenced_by.insert(*key, values.clone());
                     }
                 }
+                for (key, path) in list.paths.iter() {
+                    paths.entry(*key).or_insert_with(|| path.clone());
+                }
+            }
+            ReferencesList {
+                referenced_by,
+                paths,
             }
-            ReferencesList { referenced_by }.into()
+            .into()
         }
     })
 }
@@ -449,8 +596,12 @@ async fn top_references(list: ReferencesListVc) -> Result<ReferencesListVc> {
     }
     Ok(ReferencesList {
         referenced_by: top
+            .iter()
+            .map(|(asset, set)| (**asset, (*set).clone()))
+            .collect(),
+        paths: top
             .into_iter()
-            .map(|(asset, set)| (*asset, set.clone()))
+            .map(|(asset, _)| (*asset, list.paths[asset].clone()))
             .collect(),
     }
     .into())
@@ -463,7 +614,7 @@ async fn print_references(list: ReferencesListVc) -> Result<()> {
     for (asset, references) in list.referenced_by.iter() {
         println!(
             "{} -> {} times referenced",
-            asset.path().await?.path,
+            list.paths[asset],
             references.len()
         );
     }